@@ -1,8 +1,11 @@
 use crate::date_range::DateRange;
+use crate::schedule::{Schedule, TimeSpan};
 use chrono::naive::NaiveDate;
 use chrono::{Datelike, Month, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fmt;
 
 /*
     - offday impact is searched forward. If there is a mix of AdjustmentPolicies
@@ -15,6 +18,8 @@ pub enum AdjustmentPolicy {
     Prev,
     Next,
     Closest,
+    ModifiedNext,
+    ModifiedPrev,
     NoAdjustment,
 }
 
@@ -64,6 +69,17 @@ pub enum DateSpec {
         #[serde(default = "default_latest_date")]
         valid_until: NaiveDate,
     },
+    EasterRelative {
+        offset: i32,
+        #[serde(default)]
+        observed: AdjustmentPolicy,
+        #[serde(default)]
+        description: String,
+        #[serde(default = "default_earliest_date")]
+        valid_since: NaiveDate,
+        #[serde(default = "default_latest_date")]
+        valid_until: NaiveDate,
+    },
 }
 
 impl DateSpec {
@@ -176,6 +192,85 @@ impl DateSpec {
                     Some((result, *observed))
                 }
             }
+            EasterRelative {
+                offset,
+                valid_since,
+                valid_until,
+                observed,
+                ..
+            } => {
+                if *valid_since > end || *valid_until < start {
+                    None
+                } else {
+                    let s = if *valid_since < start {
+                        start
+                    } else {
+                        *valid_since
+                    }
+                    .year();
+
+                    let e = if *valid_until < end {
+                        *valid_until
+                    } else {
+                        end
+                    }
+                    .year();
+
+                    let mut result = Vec::new();
+                    for year in s..(e + 1) {
+                        // Anonymous Gregorian algorithm (Meeus/Jones/Butcher)
+                        let a = year % 19;
+                        let b = year / 100;
+                        let c = year % 100;
+                        let d = b / 4;
+                        let be = b % 4;
+                        let f = (b + 8) / 25;
+                        let g = (b - f + 1) / 3;
+                        let h = (19 * a + b - d - g + 15) % 30;
+                        let ci = c / 4;
+                        let k = c % 4;
+                        let l = (32 + 2 * be + 2 * ci - h - k) % 7;
+                        let m = (a + 11 * h + 22 * l) / 451;
+                        let month = (h + l - 7 * m + 114) / 31;
+                        let day = ((h + l - 7 * m + 114) % 31) + 1;
+
+                        let easter = NaiveDate::from_ymd(year, month as u32, day as u32);
+                        if let Some(date) =
+                            easter.checked_add_signed(chrono::Duration::days(*offset as i64))
+                        {
+                            result.push(date);
+                        }
+                    }
+
+                    Some((result, *observed))
+                }
+            }
+        }
+    }
+
+    /// The inclusive `[valid_since, valid_until]` span over which this spec
+    /// can produce an occurrence. A [`SpecificDate`](DateSpec::SpecificDate)
+    /// only ever occurs on its own date, so its span is just that one day.
+    fn validity_bounds(&self) -> (NaiveDate, NaiveDate) {
+        use DateSpec::*;
+
+        match self {
+            SpecificDate { date, .. } => (*date, *date),
+            DayOfMonth {
+                valid_since,
+                valid_until,
+                ..
+            }
+            | NthDayOccurance {
+                valid_since,
+                valid_until,
+                ..
+            }
+            | EasterRelative {
+                valid_since,
+                valid_until,
+                ..
+            } => (*valid_since, *valid_until),
         }
     }
 }
@@ -197,6 +292,8 @@ pub struct Calendar {
     pub exclude: Vec<DateSpec>,
     #[serde(default)]
     pub inherits: Vec<String>,
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
 }
 
 impl Calendar {
@@ -261,6 +358,26 @@ impl Calendar {
                     Some(next)
                 }
             }
+            ModifiedNext => {
+                let next = self
+                    .adjust_special_offday(date, &AdjustmentPolicy::Next, offdays)
+                    .unwrap();
+                if next.month() != date.month() || next.year() != date.year() {
+                    self.adjust_special_offday(date, &AdjustmentPolicy::Prev, offdays)
+                } else {
+                    Some(next)
+                }
+            }
+            ModifiedPrev => {
+                let prev = self
+                    .adjust_special_offday(date, &AdjustmentPolicy::Prev, offdays)
+                    .unwrap();
+                if prev.month() != date.month() || prev.year() != date.year() {
+                    self.adjust_special_offday(date, &AdjustmentPolicy::Next, offdays)
+                } else {
+                    Some(prev)
+                }
+            }
             NoAdjustment => {
                 if is_blocked(actual) {
                     None
@@ -283,14 +400,27 @@ impl Calendar {
         self.adjust_special_offdays(&offdays)
     }
 
+    /// Like [`get_special_offdays`](Self::get_special_offdays), but expands
+    /// `[start, end]` by 2 weeks on each side first, so an offday anchored
+    /// just outside the window that adjusts (forward or backward) into it is
+    /// still resolved. Every caller that resolves offdays over a bounded
+    /// window should go through this rather than `get_special_offdays`
+    /// directly, to avoid re-introducing that gap one call site at a time.
+    fn padded_offdays(&self, start: NaiveDate, end: NaiveDate) -> HashSet<NaiveDate> {
+        self.get_special_offdays(
+            start - chrono::Duration::days(14),
+            end + chrono::Duration::days(14),
+        )
+    }
+
     /// Returns true if the given date is a offday / non-business day
     fn is_offday(&self, date: NaiveDate) -> bool {
-        !self.dow.contains(&date.weekday()) || self.get_special_offdays(date, date).contains(&date)
+        !self.dow.contains(&date.weekday()) || self.padded_offdays(date, date).contains(&date)
     }
 
     /// Returns the set of non-offday calendar dates within the specified range
     pub fn date_range(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
-        let offdays = self.get_special_offdays(from, to);
+        let offdays = self.padded_offdays(from, to);
 
         // Expand by 2 weeks on each side to allow for adjustments in
         // out-of-scope periods to affect in-scope dates
@@ -301,6 +431,329 @@ impl Calendar {
             .filter(|x| *x >= from)
             .collect()
     }
+
+    /// Returns the number of business days in `[from, to)`
+    pub fn count_business_days(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        if to <= from {
+            return 0;
+        }
+        self.date_range(from, to.pred()).len() as i64
+    }
+
+    /// Steps `date` by `n` business days, skipping offdays. Positive `n`
+    /// steps forward, negative `n` steps backward.
+    pub fn add_business_days(&self, date: NaiveDate, n: i64) -> NaiveDate {
+        if n == 0 {
+            return date;
+        }
+
+        let forward = n > 0;
+        let remaining = n.abs();
+        let mut current;
+
+        // Resolve the offday set once over an expanding window so we don't
+        // re-resolve every DateSpec on each stepped day.
+        let mut window = remaining * 3 + 14;
+        loop {
+            let (start, end) = if forward {
+                (date, date + chrono::Duration::days(window))
+            } else {
+                (date - chrono::Duration::days(window), date)
+            };
+            let offdays = self.padded_offdays(start, end);
+
+            current = date;
+            let mut left = remaining;
+            let mut exhausted = false;
+            while left > 0 {
+                current = if forward { current.succ() } else { current.pred() };
+                if current < start || current > end {
+                    exhausted = true;
+                    break;
+                }
+                if self.dow.contains(&current.weekday()) && !offdays.contains(&current) {
+                    left -= 1;
+                }
+            }
+
+            if !exhausted {
+                break;
+            }
+            window *= 2;
+        }
+
+        current
+    }
+
+    /// Returns the next business day strictly after `date`
+    pub fn next_business_day(&self, date: NaiveDate) -> NaiveDate {
+        self.add_business_days(date, 1)
+    }
+
+    /// Returns the previous business day strictly before `date`
+    pub fn prev_business_day(&self, date: NaiveDate) -> NaiveDate {
+        self.add_business_days(date, -1)
+    }
+
+    /// Returns the operating-hour spans open on `date`: empty on an offday,
+    /// otherwise the result of resolving `schedule` against `date` (or no
+    /// spans at all if this calendar has no `schedule`)
+    pub fn resolve_schedule(&self, date: NaiveDate) -> Vec<TimeSpan> {
+        if self.is_offday(date) {
+            return Vec::new();
+        }
+
+        self.schedule
+            .as_ref()
+            .map(|schedule| schedule.resolve(date))
+            .unwrap_or_default()
+    }
+}
+
+impl Calendar {
+    /// Iterate over the resolved-and-adjusted occurrences of `spec`, one per
+    /// year, starting from the year containing `from` and proceeding
+    /// forward. The adjustment policy is applied independently of this
+    /// calendar's other `exclude` entries, since only a single rule is being
+    /// walked.
+    pub fn occurrences<'a>(&'a self, spec: &'a DateSpec, from: NaiveDate) -> DateSpecOccurrences<'a> {
+        DateSpecOccurrences {
+            calendar: self,
+            spec,
+            year: from.year(),
+            step: 1,
+        }
+    }
+
+    /// Returns the first occurrence of `spec` strictly after `date`
+    pub fn next_occurrence(&self, spec: &DateSpec, date: NaiveDate) -> Option<NaiveDate> {
+        // Start a year early: the prior year's occurrence may be adjusted
+        // forward across the year boundary to after `date`
+        DateSpecOccurrences {
+            calendar: self,
+            spec,
+            year: date.year() - 1,
+            step: 1,
+        }
+        .find(|d| *d > date)
+    }
+
+    /// Returns the first occurrence of `spec` strictly before `date`
+    pub fn prev_occurrence(&self, spec: &DateSpec, date: NaiveDate) -> Option<NaiveDate> {
+        // Start a year late: the following year's occurrence may be adjusted
+        // backward across the year boundary to before `date`
+        DateSpecOccurrences {
+            calendar: self,
+            spec,
+            year: date.year() + 1,
+            step: -1,
+        }
+        .find(|d| *d < date)
+    }
+}
+
+/// Iterator over the successive resolved-and-adjusted occurrences of a single
+/// [`DateSpec`] against a [`Calendar`], produced by [`Calendar::occurrences`]
+pub struct DateSpecOccurrences<'a> {
+    calendar: &'a Calendar,
+    spec: &'a DateSpec,
+    year: i32,
+    step: i32,
+}
+
+impl<'a> Iterator for DateSpecOccurrences<'a> {
+    type Item = NaiveDate;
+
+    fn next(&mut self) -> Option<NaiveDate> {
+        let (valid_since, valid_until) = self.spec.validity_bounds();
+
+        loop {
+            let year = self.year;
+
+            // Stop once the year we're about to resolve can no longer fall
+            // within the spec's validity window, rather than bailing out of
+            // the whole iterator the first time a single year comes up empty
+            // (e.g. a `SpecificDate`, or any `valid_since`/`valid_until`
+            // bound, only ever matches one year out of however many we step
+            // through).
+            if self.step > 0 && NaiveDate::from_ymd(year, 1, 1) > valid_until {
+                return None;
+            }
+            if self.step < 0 && NaiveDate::from_ymd(year, 12, 31) < valid_since {
+                return None;
+            }
+
+            self.year += self.step;
+
+            let start = NaiveDate::from_ymd(year, 1, 1);
+            let end = NaiveDate::from_ymd(year, 12, 31);
+            let (dates, policy) = match self.spec.resolve(start, end) {
+                Some(resolved) => resolved,
+                None => continue,
+            };
+
+            for date in dates {
+                if let Some(adjusted) =
+                    self.calendar
+                        .adjust_special_offday(date, &policy, &HashSet::new())
+                {
+                    return Some(adjusted);
+                }
+            }
+        }
+    }
+}
+
+/// Error produced while resolving a [`Calendar`] through a [`CalendarRegistry`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CalendarRegistryError {
+    UnknownCalendar(String),
+    InheritanceCycle(Vec<String>),
+}
+
+impl fmt::Display for CalendarRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalendarRegistryError::UnknownCalendar(name) => {
+                write!(f, "unknown calendar: {}", name)
+            }
+            CalendarRegistryError::InheritanceCycle(path) => {
+                write!(f, "inheritance cycle detected: {}", path.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for CalendarRegistryError {}
+
+/// A named collection of [`Calendar`]s that can inherit from one another via
+/// `Calendar::inherits`
+#[derive(Clone, Default, Debug)]
+pub struct CalendarRegistry {
+    calendars: HashMap<String, Calendar>,
+}
+
+impl CalendarRegistry {
+    pub fn new() -> Self {
+        CalendarRegistry {
+            calendars: HashMap::new(),
+        }
+    }
+
+    /// Add or replace a named calendar in the registry
+    pub fn insert(&mut self, name: impl Into<String>, calendar: Calendar) {
+        self.calendars.insert(name.into(), calendar);
+    }
+
+    /// Look up a calendar by name without resolving its `inherits` chain
+    pub fn get(&self, name: &str) -> Option<&Calendar> {
+        self.calendars.get(name)
+    }
+
+    /// Resolve `name` into a self-contained `Calendar`, transitively merging
+    /// each ancestor's `exclude` specs (union, ancestors first so that a
+    /// child's own entries are applied last) and `dow` set (intersection, so
+    /// a child can only be open on days all of its ancestors are also open).
+    /// Parents are merged in the order they appear in `inherits`, so the
+    /// result is deterministic. Returns an error if `name` (or any ancestor)
+    /// is missing from the registry, or if the `inherits` chain cycles back
+    /// on itself.
+    pub fn resolve(&self, name: &str) -> Result<Calendar, CalendarRegistryError> {
+        let mut visiting = Vec::new();
+        self.resolve_inner(name, &mut visiting)
+    }
+
+    fn resolve_inner(
+        &self,
+        name: &str,
+        visiting: &mut Vec<String>,
+    ) -> Result<Calendar, CalendarRegistryError> {
+        if visiting.iter().any(|n| n == name) {
+            visiting.push(name.to_owned());
+            return Err(CalendarRegistryError::InheritanceCycle(visiting.clone()));
+        }
+
+        let calendar = self
+            .calendars
+            .get(name)
+            .ok_or_else(|| CalendarRegistryError::UnknownCalendar(name.to_owned()))?;
+
+        visiting.push(name.to_owned());
+
+        let mut merged = calendar.clone();
+        let own_exclude = std::mem::take(&mut merged.exclude);
+        let mut exclude = Vec::new();
+        for parent_name in &calendar.inherits {
+            let parent = self.resolve_inner(parent_name, visiting)?;
+
+            exclude.extend(parent.exclude.clone());
+
+            merged.dow = merged.dow.intersection(&parent.dow).cloned().collect();
+        }
+        exclude.extend(own_exclude);
+        merged.exclude = exclude;
+
+        visiting.pop();
+        Ok(merged)
+    }
+}
+
+/// A [`Calendar`] precompiled over an explicit `[start, end)` span, trading
+/// the cost of resolving every `DateSpec` once up front for O(1) repeated
+/// queries. Intended for workloads that probe many dates or random-access
+/// business days across a multi-year range, where re-resolving holidays on
+/// every call (as `Calendar::is_offday`/`date_range` do) would be quadratic.
+pub struct CompiledCalendar {
+    start: NaiveDate,
+    end: NaiveDate,
+    /// Indexed by day offset from `start`; true if that day is a business day
+    is_business_day: Vec<bool>,
+    /// The business days within `[start, end)`, in order, so the nth one is a
+    /// direct O(1) lookup
+    business_days: Vec<NaiveDate>,
+}
+
+impl CompiledCalendar {
+    /// Precompute the offday set and business-day lookup tables for
+    /// `calendar` over `[start, end)`
+    pub fn compile(calendar: &Calendar, start: NaiveDate, end: NaiveDate) -> Self {
+        let offdays = calendar.padded_offdays(start, end);
+
+        let span_days = if end > start { (end - start).num_days() } else { 0 };
+
+        let mut is_business_day = Vec::with_capacity(span_days as usize);
+        let mut business_days = Vec::new();
+        let mut date = start;
+        for _ in 0..span_days {
+            let open = calendar.dow.contains(&date.weekday()) && !offdays.contains(&date);
+            is_business_day.push(open);
+            if open {
+                business_days.push(date);
+            }
+            date = date.succ();
+        }
+
+        CompiledCalendar {
+            start,
+            end,
+            is_business_day,
+            business_days,
+        }
+    }
+
+    /// Returns true if `date` is a business day, or false if it is an offday
+    /// or falls outside the compiled `[start, end)` span
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        if date < self.start || date >= self.end {
+            return false;
+        }
+        self.is_business_day[(date - self.start).num_days() as usize]
+    }
+
+    /// Returns the `n`th (0-indexed) business day within the compiled span
+    pub fn nth_business_day(&self, n: usize) -> Option<NaiveDate> {
+        self.business_days.get(n).copied()
+    }
 }
 
 #[cfg(test)]
@@ -335,6 +788,7 @@ mod tests {
                 },
             ],
             inherits: vec![],
+            schedule: None,
         };
 
         // Christmas falls on a Saturday, observed was Monday
@@ -382,6 +836,7 @@ mod tests {
                 },
             ],
             inherits: vec![],
+            schedule: None,
         };
 
         assert!(cal.is_offday(NaiveDate::from_ymd(2021, 12, 25)));
@@ -393,6 +848,498 @@ mod tests {
         assert_eq!(myrange.len(), 20);
     }
 
+    #[test]
+    fn check_modified_following_preceding() {
+        use chrono::Month::*;
+        use chrono::Weekday::*;
+
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![
+                // 2022-12-31 is a Saturday; rolling forward would land on
+                // 2023-01-02, crossing into January, so it should roll back
+                // to 2022-12-30 instead.
+                DateSpec::DayOfMonth {
+                    month: December,
+                    day: 31u32,
+                    observed: AdjustmentPolicy::ModifiedNext,
+                    description: "Month end".to_owned(),
+                    valid_since: default_earliest_date(),
+                    valid_until: default_latest_date(),
+                },
+                // 2022-05-01 is a Sunday; rolling backward would land on
+                // 2022-04-29, crossing into April, so it should roll forward
+                // to 2022-05-02 instead.
+                DateSpec::DayOfMonth {
+                    month: May,
+                    day: 1u32,
+                    observed: AdjustmentPolicy::ModifiedPrev,
+                    description: "Month start".to_owned(),
+                    valid_since: default_earliest_date(),
+                    valid_until: default_latest_date(),
+                },
+            ],
+            inherits: vec![],
+            schedule: None,
+        };
+
+        // Rolled backward to stay within December
+        assert!(cal.is_offday(NaiveDate::from_ymd(2022, 12, 30)));
+        assert!(!cal.is_offday(NaiveDate::from_ymd(2023, 1, 2)));
+
+        // Rolled forward to stay within May
+        assert!(cal.is_offday(NaiveDate::from_ymd(2022, 5, 2)));
+        assert!(!cal.is_offday(NaiveDate::from_ymd(2022, 4, 29)));
+    }
+
+    #[test]
+    fn check_business_day_arithmetic() {
+        use chrono::Weekday::*;
+
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![DateSpec::SpecificDate {
+                date: NaiveDate::from_ymd(2021, 12, 27),
+                description: "Extra holiday".to_owned(),
+            }],
+            inherits: vec![],
+            schedule: None,
+        };
+
+        // [2021-12-20 (Mon), 2021-12-25 (Sat)) holds the five weekdays Mon-Fri
+        assert_eq!(
+            cal.count_business_days(
+                NaiveDate::from_ymd(2021, 12, 20),
+                NaiveDate::from_ymd(2021, 12, 25),
+            ),
+            5
+        );
+        assert_eq!(
+            cal.count_business_days(NaiveDate::from_ymd(2021, 12, 20), NaiveDate::from_ymd(2021, 12, 20)),
+            0
+        );
+
+        // Friday the 24th, +1 business day skips the weekend and the holiday
+        assert_eq!(
+            cal.add_business_days(NaiveDate::from_ymd(2021, 12, 24), 1),
+            NaiveDate::from_ymd(2021, 12, 28)
+        );
+        assert_eq!(
+            cal.next_business_day(NaiveDate::from_ymd(2021, 12, 24)),
+            NaiveDate::from_ymd(2021, 12, 28)
+        );
+
+        // Stepping backward from the 28th lands back on the 24th
+        assert_eq!(
+            cal.add_business_days(NaiveDate::from_ymd(2021, 12, 28), -1),
+            NaiveDate::from_ymd(2021, 12, 24)
+        );
+        assert_eq!(
+            cal.prev_business_day(NaiveDate::from_ymd(2021, 12, 28)),
+            NaiveDate::from_ymd(2021, 12, 24)
+        );
+    }
+
+    #[test]
+    fn check_business_day_arithmetic_crosses_year_boundary() {
+        use chrono::Weekday::*;
+
+        // 2022-12-31 is a Saturday; observed forward it lands on 2023-01-02,
+        // a business day that a query anchored in the new year must still
+        // see as closed.
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![DateSpec::DayOfMonth {
+                month: chrono::Month::December,
+                day: 31u32,
+                observed: AdjustmentPolicy::Next,
+                description: "Year end".to_owned(),
+                valid_since: default_earliest_date(),
+                valid_until: default_latest_date(),
+            }],
+            inherits: vec![],
+            schedule: None,
+        };
+
+        // Jan 1 (Sun) and Jan 2 (observed holiday) aren't business days,
+        // leaving only Jan 3 within [2023-01-01, 2023-01-04)
+        assert_eq!(
+            cal.count_business_days(
+                NaiveDate::from_ymd(2023, 1, 1),
+                NaiveDate::from_ymd(2023, 1, 4),
+            ),
+            1
+        );
+        assert_eq!(
+            cal.add_business_days(NaiveDate::from_ymd(2023, 1, 1), 1),
+            NaiveDate::from_ymd(2023, 1, 3)
+        );
+    }
+
+    #[test]
+    fn check_registry_inheritance() {
+        use chrono::Weekday::*;
+
+        let mut registry = CalendarRegistry::new();
+
+        registry.insert(
+            "US-federal",
+            Calendar {
+                description: "US federal holidays".to_owned(),
+                dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+                public: true,
+                exclude: vec![DateSpec::SpecificDate {
+                    date: NaiveDate::from_ymd(2022, 1, 1),
+                    description: "New Years Day".to_owned(),
+                }],
+                inherits: vec![],
+                schedule: None,
+            },
+        );
+
+        registry.insert(
+            "NYSE",
+            Calendar {
+                description: "NYSE".to_owned(),
+                dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+                public: true,
+                exclude: vec![DateSpec::SpecificDate {
+                    date: NaiveDate::from_ymd(2022, 6, 20),
+                    description: "Venue closure".to_owned(),
+                }],
+                inherits: vec!["US-federal".to_owned()],
+                schedule: None,
+            },
+        );
+
+        let resolved = registry.resolve("NYSE").unwrap();
+        assert_eq!(resolved.exclude.len(), 2);
+        assert!(resolved.is_offday(NaiveDate::from_ymd(2022, 1, 1)));
+        assert!(resolved.is_offday(NaiveDate::from_ymd(2022, 6, 20)));
+    }
+
+    #[test]
+    fn check_registry_inheritance_preserves_parent_order() {
+        // With multiple parents, `exclude` must come out in declared
+        // `inherits` order (parents first, in order, then the child's own
+        // entries) so adjustment-policy searches stay deterministic.
+        let mut registry = CalendarRegistry::new();
+
+        registry.insert(
+            "A",
+            Calendar {
+                exclude: vec![DateSpec::SpecificDate {
+                    date: NaiveDate::from_ymd(2022, 1, 1),
+                    description: "A".to_owned(),
+                }],
+                ..Default::default()
+            },
+        );
+        registry.insert(
+            "B",
+            Calendar {
+                exclude: vec![DateSpec::SpecificDate {
+                    date: NaiveDate::from_ymd(2022, 2, 1),
+                    description: "B".to_owned(),
+                }],
+                ..Default::default()
+            },
+        );
+        registry.insert(
+            "child",
+            Calendar {
+                exclude: vec![DateSpec::SpecificDate {
+                    date: NaiveDate::from_ymd(2022, 3, 1),
+                    description: "child".to_owned(),
+                }],
+                inherits: vec!["A".to_owned(), "B".to_owned()],
+                ..Default::default()
+            },
+        );
+
+        let resolved = registry.resolve("child").unwrap();
+        let descriptions: Vec<&str> = resolved
+            .exclude
+            .iter()
+            .map(|spec| match spec {
+                DateSpec::SpecificDate { description, .. } => description.as_str(),
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(descriptions, vec!["A", "B", "child"]);
+    }
+
+    #[test]
+    fn check_registry_cycle_detection() {
+        let mut registry = CalendarRegistry::new();
+
+        registry.insert(
+            "a",
+            Calendar {
+                inherits: vec!["b".to_owned()],
+                ..Default::default()
+            },
+        );
+        registry.insert(
+            "b",
+            Calendar {
+                inherits: vec!["a".to_owned()],
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(
+            registry.resolve("a"),
+            Err(CalendarRegistryError::InheritanceCycle(_))
+        ));
+    }
+
+    #[test]
+    fn check_registry_unknown_calendar() {
+        let registry = CalendarRegistry::new();
+        assert!(matches!(
+            registry.resolve("missing"),
+            Err(CalendarRegistryError::UnknownCalendar(_))
+        ));
+    }
+
+    #[test]
+    fn check_date_spec_occurrences() {
+        use chrono::Month::*;
+        use chrono::Weekday::*;
+
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![],
+            inherits: vec![],
+            schedule: None,
+        };
+
+        // Thanksgiving: 4th Thursday in November
+        let thanksgiving = DateSpec::NthDayOccurance {
+            month: November,
+            dow: Thu,
+            offset: 4,
+            observed: AdjustmentPolicy::NoAdjustment,
+            description: "Thanksgiving".to_owned(),
+            valid_since: default_earliest_date(),
+            valid_until: default_latest_date(),
+        };
+
+        let occurrences: Vec<NaiveDate> = cal
+            .occurrences(&thanksgiving, NaiveDate::from_ymd(2021, 1, 1))
+            .take(3)
+            .collect();
+        assert_eq!(
+            occurrences,
+            vec![
+                NaiveDate::from_ymd(2021, 11, 25),
+                NaiveDate::from_ymd(2022, 11, 24),
+                NaiveDate::from_ymd(2023, 11, 23),
+            ]
+        );
+
+        assert_eq!(
+            cal.next_occurrence(&thanksgiving, NaiveDate::from_ymd(2021, 12, 1)),
+            Some(NaiveDate::from_ymd(2022, 11, 24))
+        );
+        assert_eq!(
+            cal.prev_occurrence(&thanksgiving, NaiveDate::from_ymd(2021, 12, 1)),
+            Some(NaiveDate::from_ymd(2021, 11, 25))
+        );
+    }
+
+    #[test]
+    fn check_occurrences_skip_years_with_no_match() {
+        // A `SpecificDate` only resolves in the single year it falls in, so
+        // every other year in the walk must be skipped rather than ending
+        // the iterator.
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]),
+            public: false,
+            exclude: vec![],
+            inherits: vec![],
+            schedule: None,
+        };
+
+        let one_off = DateSpec::SpecificDate {
+            date: NaiveDate::from_ymd(2025, 6, 2),
+            description: "One-off closure".to_owned(),
+        };
+
+        let occurrences: Vec<NaiveDate> = cal
+            .occurrences(&one_off, NaiveDate::from_ymd(2020, 1, 1))
+            .take(3)
+            .collect();
+        assert_eq!(occurrences, vec![NaiveDate::from_ymd(2025, 6, 2)]);
+
+        assert_eq!(
+            cal.next_occurrence(&one_off, NaiveDate::from_ymd(2020, 1, 1)),
+            Some(NaiveDate::from_ymd(2025, 6, 2))
+        );
+        assert_eq!(
+            cal.prev_occurrence(&one_off, NaiveDate::from_ymd(2030, 1, 1)),
+            Some(NaiveDate::from_ymd(2025, 6, 2))
+        );
+
+        // Nothing to find once the walk has passed the one-off date
+        assert_eq!(cal.next_occurrence(&one_off, NaiveDate::from_ymd(2025, 6, 2)), None);
+        assert_eq!(cal.prev_occurrence(&one_off, NaiveDate::from_ymd(2025, 6, 2)), None);
+    }
+
+    #[test]
+    fn check_resolve_schedule_empty_on_offday() {
+        use crate::schedule::{Schedule, TimeSpan};
+        use chrono::naive::NaiveTime;
+        use chrono::Weekday::*;
+
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![],
+            inherits: vec![],
+            schedule: Some(Schedule {
+                default: vec![TimeSpan {
+                    start: NaiveTime::from_hms(9, 0, 0),
+                    end: NaiveTime::from_hms(17, 0, 0),
+                    description: "Regular hours".to_owned(),
+                }],
+                overrides: vec![],
+            }),
+        };
+
+        // 2021-12-25 is a Saturday: closed regardless of the schedule
+        assert!(cal.resolve_schedule(NaiveDate::from_ymd(2021, 12, 25)).is_empty());
+
+        // A weekday falls back to the default schedule
+        let spans = cal.resolve_schedule(NaiveDate::from_ymd(2021, 12, 24));
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].start, NaiveTime::from_hms(9, 0, 0));
+        assert_eq!(spans[0].end, NaiveTime::from_hms(17, 0, 0));
+    }
+
+    #[test]
+    fn check_resolve_schedule_sees_adjustment_from_prior_year() {
+        use crate::schedule::{Schedule, TimeSpan};
+        use chrono::naive::NaiveTime;
+        use chrono::Weekday::*;
+
+        // 2022-12-31 is a Saturday; observed forward it lands on 2023-01-02,
+        // a date whose own year has no candidate Dec-31 occurrence to find.
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![DateSpec::DayOfMonth {
+                month: chrono::Month::December,
+                day: 31u32,
+                observed: AdjustmentPolicy::Next,
+                description: "Year end".to_owned(),
+                valid_since: default_earliest_date(),
+                valid_until: default_latest_date(),
+            }],
+            inherits: vec![],
+            schedule: Some(Schedule {
+                default: vec![TimeSpan {
+                    start: NaiveTime::from_hms(9, 0, 0),
+                    end: NaiveTime::from_hms(17, 0, 0),
+                    description: "Regular hours".to_owned(),
+                }],
+                overrides: vec![],
+            }),
+        };
+
+        assert!(cal.is_offday(NaiveDate::from_ymd(2023, 1, 2)));
+        assert!(cal.resolve_schedule(NaiveDate::from_ymd(2023, 1, 2)).is_empty());
+    }
+
+    #[test]
+    fn check_easter_relative() {
+        use chrono::Weekday::*;
+
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![
+                DateSpec::EasterRelative {
+                    offset: -2,
+                    observed: AdjustmentPolicy::NoAdjustment,
+                    description: "Good Friday".to_owned(),
+                    valid_since: default_earliest_date(),
+                    valid_until: default_latest_date(),
+                },
+                DateSpec::EasterRelative {
+                    offset: 1,
+                    observed: AdjustmentPolicy::NoAdjustment,
+                    description: "Easter Monday".to_owned(),
+                    valid_since: default_earliest_date(),
+                    valid_until: default_latest_date(),
+                },
+            ],
+            inherits: vec![],
+            schedule: None,
+        };
+
+        // Easter Sunday 2022 is 2022-04-17
+        assert!(cal.is_offday(NaiveDate::from_ymd(2022, 4, 15)));
+        assert!(cal.is_offday(NaiveDate::from_ymd(2022, 4, 18)));
+
+        // The preceding Thursday is an ordinary business day
+        assert!(!cal.is_offday(NaiveDate::from_ymd(2022, 4, 14)));
+    }
+
+    #[test]
+    fn check_compiled_calendar() {
+        use chrono::Weekday::*;
+
+        let cal = Calendar {
+            description: "Test description".to_owned(),
+            dow: HashSet::from([Mon, Tue, Wed, Thu, Fri]),
+            public: false,
+            exclude: vec![DateSpec::SpecificDate {
+                date: NaiveDate::from_ymd(2021, 12, 27),
+                description: "Extra holiday".to_owned(),
+            }],
+            inherits: vec![],
+            schedule: None,
+        };
+
+        let compiled = CompiledCalendar::compile(
+            &cal,
+            NaiveDate::from_ymd(2021, 12, 20),
+            NaiveDate::from_ymd(2021, 12, 29),
+        );
+
+        assert!(compiled.is_business_day(NaiveDate::from_ymd(2021, 12, 20)));
+        assert!(!compiled.is_business_day(NaiveDate::from_ymd(2021, 12, 25)));
+        assert!(!compiled.is_business_day(NaiveDate::from_ymd(2021, 12, 27)));
+        // Outside the compiled span
+        assert!(!compiled.is_business_day(NaiveDate::from_ymd(2022, 1, 1)));
+
+        // Business days in range: 20,21,22,23,24 (Mon-Fri), then 28 (Tue)
+        assert_eq!(compiled.nth_business_day(0), Some(NaiveDate::from_ymd(2021, 12, 20)));
+        assert_eq!(compiled.nth_business_day(4), Some(NaiveDate::from_ymd(2021, 12, 24)));
+        assert_eq!(compiled.nth_business_day(5), Some(NaiveDate::from_ymd(2021, 12, 28)));
+        assert_eq!(compiled.nth_business_day(6), None);
+    }
+
     #[test]
     fn test_deserialization() {
         let data = r#"
@@ -2,16 +2,18 @@ use chrono::naive::{NaiveDate, NaiveTime};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
-struct TimeSpan {
-    start: NaiveTime,
-    end: NaiveTime,
+pub struct TimeSpan {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
     #[serde(default)]
-    description: String,
+    pub description: String,
 }
 
 impl TimeSpan {
+    /// The overlapping portion of `self` and `other`, or `None` if they
+    /// don't overlap at all
     fn intersection(&self, other: &TimeSpan) -> Option<TimeSpan> {
-        if self.end < other.start {
+        if self.end < other.start || other.end < self.start {
             None
         } else {
             Some(TimeSpan {
@@ -32,6 +34,26 @@ impl TimeSpan {
             })
         }
     }
+
+    /// Merge `self` and `other` into a single span if they overlap or touch,
+    /// or `None` if a gap separates them
+    fn union(&self, other: &TimeSpan) -> Option<TimeSpan> {
+        self.intersection(other)?;
+
+        Some(TimeSpan {
+            start: if self.start < other.start {
+                self.start
+            } else {
+                other.start
+            },
+            end: if self.end > other.end {
+                self.end
+            } else {
+                other.end
+            },
+            description: format!("Union of {} and {}", self.description, other.description),
+        })
+    }
 }
 
 impl PartialEq for TimeSpan {
@@ -41,19 +63,72 @@ impl PartialEq for TimeSpan {
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
-struct ScheduleOverride {
-    start_date: NaiveDate,
-    end_date: Option<NaiveDate>,
-    schedule: Vec<TimeSpan>,
+pub struct ScheduleOverride {
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub schedule: Vec<TimeSpan>,
 
     #[serde(default)]
-    description: String,
+    pub description: String,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
-struct Schedule {
-    default: Vec<TimeSpan>,
-    overrides: Vec<ScheduleOverride>,
+pub struct Schedule {
+    pub default: Vec<TimeSpan>,
+    pub overrides: Vec<ScheduleOverride>,
+}
+
+impl Schedule {
+    /// Resolve the operating-hour spans in effect on `date`: the most
+    /// specific applicable `ScheduleOverride` (the one covering the
+    /// narrowest date range, with open-ended overrides treated as least
+    /// specific), falling back to `default` if none apply. The result is
+    /// sorted and overlapping spans are coalesced.
+    pub fn resolve(&self, date: NaiveDate) -> Vec<TimeSpan> {
+        let spans = self.applicable_spans(date);
+        Self::normalize(spans)
+    }
+
+    fn applicable_spans(&self, date: NaiveDate) -> Vec<TimeSpan> {
+        let mut candidates: Vec<&ScheduleOverride> = self
+            .overrides
+            .iter()
+            .filter(|o| {
+                o.start_date <= date
+                    && match o.end_date {
+                        Some(end) => date <= end,
+                        None => true,
+                    }
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return self.default.clone();
+        }
+
+        candidates.sort_by_key(|o| match o.end_date {
+            Some(end) => (end - o.start_date).num_days(),
+            None => i64::MAX,
+        });
+
+        candidates[0].schedule.clone()
+    }
+
+    fn normalize(mut spans: Vec<TimeSpan>) -> Vec<TimeSpan> {
+        spans.sort_by_key(|s| s.start);
+
+        let mut merged: Vec<TimeSpan> = Vec::new();
+        for span in spans {
+            match merged.last_mut() {
+                Some(last) if last.union(&span).is_some() => {
+                    *last = last.union(&span).unwrap();
+                }
+                _ => merged.push(span),
+            }
+        }
+
+        merged
+    }
 }
 
 #[cfg(test)]
@@ -84,4 +159,110 @@ mod tests {
             assert_eq!(a.intersection(&b), Some(c));
         }
     }
+
+    #[test]
+    fn test_schedule_resolve_default() {
+        let schedule = Schedule {
+            default: vec![TimeSpan {
+                start: NaiveTime::from_hms(9, 0, 0),
+                end: NaiveTime::from_hms(17, 0, 0),
+                description: "Regular hours".to_owned(),
+            }],
+            overrides: vec![],
+        };
+
+        let spans = schedule.resolve(NaiveDate::from_ymd(2022, 1, 3));
+        assert_eq!(
+            spans,
+            vec![TimeSpan {
+                start: NaiveTime::from_hms(9, 0, 0),
+                end: NaiveTime::from_hms(17, 0, 0),
+                description: "".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_schedule_resolve_picks_most_specific_override() {
+        let schedule = Schedule {
+            default: vec![TimeSpan {
+                start: NaiveTime::from_hms(9, 0, 0),
+                end: NaiveTime::from_hms(17, 0, 0),
+                description: "Regular hours".to_owned(),
+            }],
+            overrides: vec![
+                ScheduleOverride {
+                    start_date: NaiveDate::from_ymd(2022, 1, 1),
+                    end_date: None,
+                    schedule: vec![TimeSpan {
+                        start: NaiveTime::from_hms(10, 0, 0),
+                        end: NaiveTime::from_hms(14, 0, 0),
+                        description: "Open-ended override".to_owned(),
+                    }],
+                    description: "Open-ended".to_owned(),
+                },
+                ScheduleOverride {
+                    start_date: NaiveDate::from_ymd(2022, 12, 24),
+                    end_date: Some(NaiveDate::from_ymd(2022, 12, 24)),
+                    schedule: vec![TimeSpan {
+                        start: NaiveTime::from_hms(9, 0, 0),
+                        end: NaiveTime::from_hms(12, 0, 0),
+                        description: "Half day".to_owned(),
+                    }],
+                    description: "Christmas Eve".to_owned(),
+                },
+            ],
+        };
+
+        // The single-day override is more specific than the open-ended one
+        let spans = schedule.resolve(NaiveDate::from_ymd(2022, 12, 24));
+        assert_eq!(
+            spans,
+            vec![TimeSpan {
+                start: NaiveTime::from_hms(9, 0, 0),
+                end: NaiveTime::from_hms(12, 0, 0),
+                description: "".to_owned(),
+            }]
+        );
+
+        // Outside the single-day override, the open-ended one still applies
+        let spans = schedule.resolve(NaiveDate::from_ymd(2022, 6, 1));
+        assert_eq!(
+            spans,
+            vec![TimeSpan {
+                start: NaiveTime::from_hms(10, 0, 0),
+                end: NaiveTime::from_hms(14, 0, 0),
+                description: "".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_schedule_resolve_coalesces_overlaps() {
+        let schedule = Schedule {
+            default: vec![
+                TimeSpan {
+                    start: NaiveTime::from_hms(9, 0, 0),
+                    end: NaiveTime::from_hms(12, 0, 0),
+                    description: "Morning".to_owned(),
+                },
+                TimeSpan {
+                    start: NaiveTime::from_hms(11, 0, 0),
+                    end: NaiveTime::from_hms(17, 0, 0),
+                    description: "Afternoon".to_owned(),
+                },
+            ],
+            overrides: vec![],
+        };
+
+        let spans = schedule.resolve(NaiveDate::from_ymd(2022, 1, 3));
+        assert_eq!(
+            spans,
+            vec![TimeSpan {
+                start: NaiveTime::from_hms(9, 0, 0),
+                end: NaiveTime::from_hms(17, 0, 0),
+                description: "".to_owned(),
+            }]
+        );
+    }
 }